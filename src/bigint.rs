@@ -0,0 +1,229 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+
+// Arbitrary-precision integer: a sign plus a little-endian, base-1e9
+// magnitude. Base 1e9 (rather than a power of two) means formatting and
+// decimal parsing never need a base conversion, just chunking.
+//
+// `magnitude` never has a trailing zero limb; zero is `negative: false,
+// magnitude: []`, so there is exactly one representation of zero.
+const BASE: u64 = 1_000_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => BigInt::cmp_magnitude(&self.magnitude, &other.magnitude),
+            (true, true) => BigInt::cmp_magnitude(&other.magnitude, &self.magnitude)
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl BigInt {
+    fn normalize(mut negative: bool, mut magnitude: Vec<u32>) -> BigInt {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        if magnitude.is_empty() {
+            negative = false;
+        }
+        BigInt { negative, magnitude }
+    }
+
+    pub(crate) fn neg(&self) -> BigInt {
+        BigInt::normalize(!self.negative, self.magnitude.clone())
+    }
+
+    fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+        if a.len() != b.len() {
+            return a.len().cmp(&b.len());
+        }
+        for i in (0..a.len()).rev() {
+            if a[i] != b[i] {
+                return a[i].cmp(&b[i]);
+            }
+        }
+        Ordering::Equal
+    }
+
+    fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+        let mut carry = 0u64;
+        for i in 0..a.len().max(b.len()) {
+            let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+            result.push((sum % BASE) as u32);
+            carry = sum / BASE;
+        }
+        if carry > 0 {
+            result.push(carry as u32);
+        }
+        result
+    }
+
+    // Precondition: the magnitude of `a` is >= the magnitude of `b`.
+    fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut result = Vec::with_capacity(a.len());
+        let mut borrow = 0i64;
+        for i in 0..a.len() {
+            let mut diff = a[i] as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+            if diff < 0 {
+                diff += BASE as i64;
+                borrow = 1;
+            } else {
+                borrow = 0;
+            }
+            result.push(diff as u32);
+        }
+        result
+    }
+
+    pub(crate) fn add(&self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            BigInt::normalize(self.negative, BigInt::add_magnitude(&self.magnitude, &other.magnitude))
+        } else {
+            match BigInt::cmp_magnitude(&self.magnitude, &other.magnitude) {
+                Ordering::Equal => BigInt::normalize(false, Vec::new()),
+                Ordering::Greater => BigInt::normalize(self.negative, BigInt::sub_magnitude(&self.magnitude, &other.magnitude)),
+                Ordering::Less => BigInt::normalize(other.negative, BigInt::sub_magnitude(&other.magnitude, &self.magnitude))
+            }
+        }
+    }
+
+    pub(crate) fn sub(&self, other: &BigInt) -> BigInt {
+        self.add(&other.neg())
+    }
+
+    /// The value as a `usize`, if it's nonnegative and fits — used to
+    /// extract opcodes, selectors and arities from an atom without caring
+    /// how many limbs the atom actually has.
+    pub(crate) fn to_small_usize(&self) -> Option<usize> {
+        if self.negative {
+            return None;
+        }
+        let mut value: u64 = 0;
+        for &limb in self.magnitude.iter().rev() {
+            value = value.checked_mul(BASE)?.checked_add(limb as u64)?;
+        }
+        usize::try_from(value).ok()
+    }
+
+    /// The value as an `i32`, if it fits — used where callers want the
+    /// exact signed value of a (presumably small) atom.
+    pub(crate) fn to_i32(&self) -> Option<i32> {
+        let mut value: i64 = 0;
+        for &limb in self.magnitude.iter().rev() {
+            value = value.checked_mul(BASE as i64)?.checked_add(limb as i64)?;
+        }
+        if self.negative {
+            value = -value;
+        }
+        i32::try_from(value).ok()
+    }
+
+    /// Parses an unsigned run of ASCII decimal digits (as produced by the
+    /// tokenizer) into a `BigInt` of the given sign.
+    pub(crate) fn from_decimal_digits(negative: bool, digits: &str) -> BigInt {
+        let bytes = digits.as_bytes();
+        let mut magnitude = Vec::new();
+        let mut end = bytes.len();
+        while end > 0 {
+            let start = end.saturating_sub(9);
+            let chunk = std::str::from_utf8(&bytes[start..end]).expect("ASCII decimal digits are valid UTF-8");
+            magnitude.push(chunk.parse::<u32>().expect("tokenizer only ever produces ASCII digits"));
+            end = start;
+        }
+        BigInt::normalize(negative, magnitude)
+    }
+}
+
+impl From<i32> for BigInt {
+    fn from(n: i32) -> BigInt {
+        let negative = n < 0;
+        let mut remaining = (n as i64).unsigned_abs();
+        let mut magnitude = Vec::new();
+        while remaining > 0 {
+            magnitude.push((remaining % BASE) as u32);
+            remaining /= BASE;
+        }
+        BigInt::normalize(negative, magnitude)
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.magnitude.is_empty() {
+            return write!(f, "0");
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let mut limbs = self.magnitude.iter().rev();
+        write!(f, "{}", limbs.next().expect("magnitude is non-empty"))?;
+        for limb in limbs {
+            write!(f, "{:09}", limb)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BigInt;
+
+    #[test]
+    fn round_trips_small_integers_through_i32() {
+        for n in [0, 1, -1, 5, -5, i32::MAX, i32::MIN] {
+            assert_eq!(BigInt::from(n).to_i32(), Some(n));
+        }
+    }
+
+    #[test]
+    fn subtracts_beyond_i32_range() {
+        let a = BigInt::from(i32::MIN);
+        let b = BigInt::from(1);
+        let r = a.sub(&b);
+
+        assert_eq!(r.to_i32(), None);
+        assert_eq!(format!("{}", r), "-2147483649");
+    }
+
+    #[test]
+    fn negates_and_subtracts() {
+        assert_eq!(format!("{}", BigInt::from(5).neg()), "-5");
+        assert_eq!(format!("{}", BigInt::from(3).sub(&BigInt::from(10))), "-7");
+        assert_eq!(format!("{}", BigInt::from(-3).sub(&BigInt::from(-10))), "7");
+    }
+
+    #[test]
+    fn extracts_small_opcodes() {
+        assert_eq!(BigInt::from(5).to_small_usize(), Some(5));
+        assert_eq!(BigInt::from(-1).to_small_usize(), None);
+    }
+
+    #[test]
+    fn orders_by_numeric_value() {
+        assert!(BigInt::from(-1) < BigInt::from(1));
+        assert!(BigInt::from(-5) < BigInt::from(-1));
+        assert!(BigInt::from_decimal_digits(false, "123456789012345678901234567890") > BigInt::from(i32::MAX));
+    }
+
+    #[test]
+    fn decimal_round_trip() {
+        let big = BigInt::from_decimal_digits(true, "123456789012345678901234567890");
+        assert_eq!(format!("{}", big), "-123456789012345678901234567890");
+    }
+}
@@ -1,19 +1,327 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{ Hash, Hasher };
 use std::rc::{ Rc };
 
+mod parse;
+mod bigint;
+
+pub(crate) use self::parse::{ parse, ParseError };
+use self::bigint::BigInt;
+
 #[derive(Debug, Clone)]
 enum Value
 {
-    Error,
-    Atom(i32),
-    Cell(Rc<Value>, Rc<Value>)
+    Error(Rc<ErrorInfo>),
+    Atom(Rc<BigInt>),
+    Cell(Rc<Value>, Rc<Value>),
+    // De Bruijn reference to the `n`-th most recently bound argument.
+    Var(usize),
+    Lambda(Rc<Closure>)
+}
+
+use self::Value::{ Error, Atom, Cell, Var, Lambda };
+
+/// Builds an `Atom` from a plain integer; shorthand for the common case of
+/// needing a small literal (an opcode, a selector, `0`/`1` for booleans)
+/// rather than a value that came from `sub`.
+fn atom(n: i32) -> Value {
+    Atom(Rc::new(BigInt::from(n)))
+}
+
+/// A closure: a lambda body paired with the `Env` it was built in, so
+/// applying it later sees the bindings that were in scope at construction
+/// time rather than whatever happens to be in scope at the call site.
+#[derive(Debug)]
+struct Closure {
+    arity: usize,
+    body: Rc<Value>,
+    env: Rc<Env>
+}
+
+impl PartialEq for Closure {
+    fn eq(&self, other: &Closure) -> bool {
+        self.arity == other.arity && self.body == other.body && self.env == other.env
+    }
+}
+
+impl Eq for Closure {}
+
+impl Ord for Closure {
+    fn cmp(&self, other: &Closure) -> Ordering {
+        self.arity.cmp(&other.arity)
+            .then_with(|| self.body.cmp(&other.body))
+            .then_with(|| self.env.cmp(&other.env))
+    }
 }
 
-use self::Value::{ Error, Atom, Cell };
+impl PartialOrd for Closure {
+    fn partial_cmp(&self, other: &Closure) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for Closure {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.arity.hash(state);
+        self.body.hash(state);
+        self.env.hash(state);
+    }
+}
+
+/// Bound arguments, most recently pushed first. `Var(0)` reads the head of
+/// the current `Env`, `Var(1)` the one behind it, and so on.
+#[derive(Debug)]
+enum Env {
+    Empty,
+    Frame(Rc<Value>, Rc<Env>)
+}
+
+impl Env {
+    fn get(&self, index: usize) -> Option<Rc<Value>> {
+        match self {
+            &Env::Empty => None,
+            &Env::Frame(ref v, ref rest) => {
+                if index == 0 { Some(v.clone()) } else { rest.get(index - 1) }
+            }
+        }
+    }
+}
+
+impl PartialEq for Env {
+    fn eq(&self, other: &Env) -> bool {
+        match (self, other) {
+            (&Env::Empty, &Env::Empty) => true,
+            (&Env::Frame(ref v1, ref rest1), &Env::Frame(ref v2, ref rest2)) => v1 == v2 && rest1 == rest2,
+            _ => false
+        }
+    }
+}
+
+impl Eq for Env {}
+
+impl Ord for Env {
+    fn cmp(&self, other: &Env) -> Ordering {
+        match (self, other) {
+            (&Env::Empty, &Env::Empty) => Ordering::Equal,
+            (&Env::Empty, &Env::Frame(_, _)) => Ordering::Less,
+            (&Env::Frame(_, _), &Env::Empty) => Ordering::Greater,
+            (&Env::Frame(ref v1, ref rest1), &Env::Frame(ref v2, ref rest2)) =>
+                v1.cmp(v2).then_with(|| rest1.cmp(rest2))
+        }
+    }
+}
+
+impl PartialOrd for Env {
+    fn partial_cmp(&self, other: &Env) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for Env {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            &Env::Empty => 0u8.hash(state),
+            &Env::Frame(ref v, ref rest) => {
+                1u8.hash(state);
+                v.hash(state);
+                rest.hash(state);
+            }
+        }
+    }
+}
+
+/// Why a reduction produced `Error`, plus (when there is one) the subtree
+/// that caused it.
+#[derive(Debug, Clone)]
+struct ErrorInfo {
+    kind: ErrorKind,
+    subtree: Option<Rc<Value>>
+}
+
+#[derive(Debug, Clone)]
+enum ErrorKind {
+    // Written directly in source text (`!` or `_`); there's no failed
+    // reduction to blame.
+    Literal,
+    UnknownOpcode(usize),
+    TypeMismatch,
+    MalformedOperand,
+    DivergentEq,
+    UnboundVariable(usize),
+    NotAFunction
+}
+
+fn err(kind: ErrorKind, subtree: Option<Rc<Value>>) -> Value {
+    Error(Rc::new(ErrorInfo { kind, subtree }))
+}
+
+impl fmt::Display for Value {
+    // Prints the minimal right-associative form, i.e. the inverse of `parse`:
+    // `Cell(a, Cell(b, c))` prints as `[a b c]`, not `[a [b c]]`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &Error(_) => write!(f, "!"),
+            &Atom(ref a) => write!(f, "{}", a),
+            &Var(n) => write!(f, "${}", n),
+            &Lambda(ref c) => write!(f, "<lambda/{}>", c.arity),
+            &Cell(ref a, ref b) => {
+                write!(f, "[{}", a)?;
+                let mut tail = b.clone();
+                loop {
+                    match *tail {
+                        Cell(ref a, ref b) => {
+                            write!(f, " {}", a)?;
+                            tail = b.clone();
+                        }
+                        _ => {
+                            write!(f, " {}", tail)?;
+                            break;
+                        }
+                    }
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+// Structural equality: both Errors are equal regardless of kind/subtree
+// (an `Error` carries no comparable value, only a reason), atoms compare by
+// numeric value, cells componentwise, closures by (arity, body, env).
+//
+// Iterative (an explicit work stack of the pairs still to compare), not
+// plain recursion, so two deeply nested `Cell` chains can be compared
+// without overflowing the Rust call stack — the same reason `sub_cell`/
+// `eq_cell` are iterative. Compares left to right and short-circuits on the
+// first unequal pair found.
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        let mut work = vec![(self, other)];
+
+        while let Some((a, b)) = work.pop() {
+            match (a, b) {
+                (&Error(_), &Error(_)) => {}
+                (&Atom(ref a), &Atom(ref b)) => if a != b { return false; },
+                (&Cell(ref a1, ref b1), &Cell(ref a2, ref b2)) => {
+                    work.push((b1.as_ref(), b2.as_ref()));
+                    work.push((a1.as_ref(), a2.as_ref()));
+                }
+                (&Var(a), &Var(b)) => if a != b { return false; },
+                (&Lambda(ref a), &Lambda(ref b)) => if a != b { return false; },
+                _ => return false
+            }
+        }
+
+        true
+    }
+}
+
+impl Eq for Value {}
+
+// Consistent with `PartialEq`: every `Error` hashes the same way regardless
+// of its kind/subtree, since every `Error` compares equal regardless of
+// those fields too (a derived `Hash` would break the `Hash`/`Eq` contract
+// here: equal values must hash equally).
+//
+// Iterative for the same reason `PartialEq` above is: a deep `Cell` chain
+// must hash without overflowing the Rust call stack.
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut work = vec![self];
+
+        while let Some(v) = work.pop() {
+            match v {
+                &Error(_) => 0u8.hash(state),
+                &Atom(ref a) => { 1u8.hash(state); a.hash(state); }
+                &Cell(ref a, ref b) => {
+                    2u8.hash(state);
+                    work.push(b.as_ref());
+                    work.push(a.as_ref());
+                }
+                &Var(n) => { 3u8.hash(state); n.hash(state); }
+                &Lambda(ref c) => { 4u8.hash(state); c.hash(state); }
+            }
+        }
+    }
+}
+
+impl Value {
+    // Fixed variant order for `Ord`, lowest first. `Var`/`Lambda` aren't
+    // mentioned by name in any spec for this ordering; they're simply placed
+    // after the three original shapes.
+    fn variant_rank(&self) -> u8 {
+        match self {
+            &Error(_) => 0,
+            &Atom(_) => 1,
+            &Cell(_, _) => 2,
+            &Var(_) => 3,
+            &Lambda(_) => 4
+        }
+    }
+}
+
+// Total order: `Error < Atom < Cell < Var < Lambda`, atoms by numeric value,
+// cells lexicographically on (head, tail).
+//
+// Iterative for the same reason `PartialEq`/`Hash` above are. Mirrors
+// `a1.cmp(a2).then_with(|| b1.cmp(b2))`'s short-circuiting: `(a1, a2)` is
+// pushed last so it's compared (and, if it's itself a `Cell`, fully
+// expanded) before `(b1, b2)` is ever popped, and any non-`Equal` result
+// returns immediately instead of exploring the rest of the stack.
+impl Ord for Value {
+    fn cmp(&self, other: &Value) -> Ordering {
+        let mut work = vec![(self, other)];
+
+        while let Some((a, b)) = work.pop() {
+            let ord = match (a, b) {
+                (&Error(_), &Error(_)) => Ordering::Equal,
+                (&Atom(ref a), &Atom(ref b)) => a.cmp(b),
+                (&Cell(ref a1, ref b1), &Cell(ref a2, ref b2)) => {
+                    work.push((b1.as_ref(), b2.as_ref()));
+                    work.push((a1.as_ref(), a2.as_ref()));
+                    Ordering::Equal
+                }
+                (&Var(a), &Var(b)) => a.cmp(&b),
+                (&Lambda(ref a), &Lambda(ref b)) => a.cmp(b),
+                _ => a.variant_rank().cmp(&b.variant_rank())
+            };
+
+            if ord != Ordering::Equal {
+                return ord;
+            }
+        }
+
+        Ordering::Equal
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Value) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Lets callers write `v == 1` instead of `v.atom_value() == Some(1)`.
+impl PartialEq<i32> for Value {
+    fn eq(&self, other: &i32) -> bool {
+        match self {
+            &Atom(ref a) => **a == BigInt::from(*other),
+            _ => false
+        }
+    }
+}
+
+impl PartialEq<Value> for i32 {
+    fn eq(&self, other: &Value) -> bool {
+        other == self
+    }
+}
 
 impl Value {
     fn atom_value(&self) -> Option<i32> {
         match self {
-            &Atom(a) => Some(a),
+            &Atom(ref n) => n.to_i32(),
             _ => None
         }
     }
@@ -26,155 +334,445 @@ impl Value {
     }
 }
 
+/// The small nonnegative integer (an opcode, a selector, an arity) this
+/// atom names, or `None` if it isn't an atom or doesn't fit (negative, or
+/// too large for a `usize`) — callers turn `None` into an `Error`: a
+/// `TypeMismatch` in opcode position, a `MalformedOperand` for a selector
+/// or an arity.
+fn opcode(v: &Value) -> Option<usize> {
+    match v {
+        &Atom(ref n) => n.to_small_usize(),
+        _ => None
+    }
+}
+
 fn kind(v: &Value) -> Value {
     match v {
-        &Error => Error,
-        &Atom(_) => Atom(0),
-        &Cell(_, _) => Atom(1),
+        &Error(ref info) => Error(info.clone()),
+        &Atom(_) => atom(0),
+        &Cell(_, _) => atom(1),
+        &Var(_) | &Lambda(_) => err(ErrorKind::TypeMismatch, Some(Rc::new(v.clone())))
     }
 }
 
 fn sub(v: &Value) -> Value {
     match v {
-        &Error => Error,
+        &Error(ref info) => Error(info.clone()),
 
         // math- a
-        &Atom(v) => Atom(-v),
+        &Atom(ref n) => Atom(Rc::new(n.neg())),
 
         // math- [a, b]
         // math- [[a, b], c]
         // math- [a, [b, c]]
         // math- [[a, b], [c, d]]
-        &Cell(ref a, ref b) => sub_cell(&a, &b)
+        &Cell(ref a, ref b) => sub_cell(&a, &b),
+
+        &Var(_) | &Lambda(_) => err(ErrorKind::TypeMismatch, Some(Rc::new(v.clone())))
     }
 }
 
-// Equivalent to calling sub(Cell(a, b))
+// Equivalent to calling sub(Cell(a, b)).
+//
+// Iterative (an explicit work/result stack), not plain recursion, for the
+// same reason `substitute`/`eval_trampoline` are: a deeply nested Cell tree
+// must reduce without overflowing the Rust call stack.
 fn sub_cell(a: &Value, b: &Value) -> Value {
-    match (a, b) {
-        // math- [a, b]
-        (&Atom(a), &Atom(b)) => Atom(a - b),
+    enum Step {
+        Visit(Rc<Value>, Rc<Value>),
+        BuildCell
+    }
 
-        // math- [[a, b], c] => [math- [a, c], math- [b, c]]
-        (&Cell(ref a, ref b), &Atom(c)) => Cell(
-            Rc::new(sub_cell(&a, &Atom(c))),
-            Rc::new(sub_cell(&b, &Atom(c)))
-        ),
+    let mut work = vec![Step::Visit(Rc::new(a.clone()), Rc::new(b.clone()))];
+    let mut results: Vec<Rc<Value>> = Vec::new();
 
-        // math- [a, [b, c]] => [math- [a, b], math- [a, c]]
-        (&Atom(a), &Cell(ref b, ref c)) => Cell(
-            Rc::new(sub_cell(&Atom(a), &b)),
-            Rc::new(sub_cell(&Atom(a), &c))
-        ),
+    while let Some(step) = work.pop() {
+        match step {
+            Step::Visit(a, b) => match (&*a, &*b) {
+                // math- [a, b]
+                (&Atom(ref a), &Atom(ref b)) => results.push(Rc::new(Atom(Rc::new(a.sub(b))))),
 
-        // math- [[a, b], [c, d]] => [math- [a, c], math- [b, d]]
-        (&Cell(ref a, ref b), &Cell(ref c, ref d)) => Cell(
-            Rc::new(sub_cell(&a, &c)),
-            Rc::new(sub_cell(&b, &d)),
-        ),
+                // math- [[a, b], c] => [math- [a, c], math- [b, c]]
+                (&Cell(ref a1, ref b1), &Atom(_)) => {
+                    work.push(Step::BuildCell);
+                    work.push(Step::Visit(b1.clone(), b.clone()));
+                    work.push(Step::Visit(a1.clone(), b.clone()));
+                }
+
+                // math- [a, [b, c]] => [math- [a, b], math- [a, c]]
+                (&Atom(_), &Cell(ref b1, ref c1)) => {
+                    work.push(Step::BuildCell);
+                    work.push(Step::Visit(a.clone(), c1.clone()));
+                    work.push(Step::Visit(a.clone(), b1.clone()));
+                }
 
-        _ => Error
+                // math- [[a, b], [c, d]] => [math- [a, c], math- [b, d]]
+                (&Cell(ref a1, ref b1), &Cell(ref c1, ref d1)) => {
+                    work.push(Step::BuildCell);
+                    work.push(Step::Visit(b1.clone(), d1.clone()));
+                    work.push(Step::Visit(a1.clone(), c1.clone()));
+                }
+
+                // One side is Error, or otherwise not a shape math- knows
+                // how to recurse into.
+                _ => results.push(Rc::new(err(ErrorKind::TypeMismatch, Some(Rc::new(Cell(a.clone(), b.clone()))))))
+            },
+            Step::BuildCell => {
+                let b = results.pop().expect("BuildCell pushed only after both sides compute");
+                let a = results.pop().expect("BuildCell pushed only after both sides compute");
+                results.push(Rc::new(Cell(a, b)));
+            }
+        }
     }
+
+    (*results.pop().expect("the initial Visit step always produces a value")).clone()
 }
 
 fn eq(v: &Value) -> Value {
     match v {
-        &Error => Error,
+        &Error(ref info) => Error(info.clone()),
 
         // math= a => Error
-        &Atom(v) => Error,
+        &Atom(_) => err(ErrorKind::DivergentEq, Some(Rc::new(v.clone()))),
 
         // math= [a, a] => 1
         // math= [a, b] => 0
         // math= [[a, b], [c, d]] => math= [math= [a, c], math= [b, d]]
-        &Cell(ref a, ref b) => eq_cell(&a, &b)
+        &Cell(ref a, ref b) => eq_cell(&a, &b),
+
+        &Var(_) | &Lambda(_) => err(ErrorKind::TypeMismatch, Some(Rc::new(v.clone())))
     }
 }
 
+// Iterative (an explicit work/result stack), not plain recursion, for the
+// same reason `sub_cell` is: a deeply nested Cell tree must reduce without
+// overflowing the Rust call stack.
 fn eq_cell(a: &Value, b: &Value) -> Value {
-    match (a, b) {
+    enum Step {
+        Visit(Rc<Value>, Rc<Value>),
+        Combine
+    }
 
-        // math= [a, a] => 1
-        // math= [a, b] => 0
-        (&Atom(a), &Atom(b)) => Atom(if a == b { 1 } else { 0 }),
+    let mut work = vec![Step::Visit(Rc::new(a.clone()), Rc::new(b.clone()))];
+    let mut results: Vec<Rc<Value>> = Vec::new();
 
-        // math= [[a, b], [c, d]] => math= [math= [a, c], math= [b, d]]
-        (&Cell(ref a, ref b), &Cell(ref c, ref d)) => eq_cell(
-            &eq_cell(a, c),
-            &eq_cell(b, d)
-        ),
+    while let Some(step) = work.pop() {
+        match step {
+            Step::Visit(a, b) => match (&*a, &*b) {
+                // math= [a, a] => 1
+                // math= [a, b] => 0
+                (&Atom(ref a), &Atom(ref b)) => results.push(Rc::new(atom(if a == b { 1 } else { 0 }))),
+
+                // math= [[a, b], [c, d]] => math= [math= [a, c], math= [b, d]]
+                (&Cell(ref a1, ref b1), &Cell(ref c1, ref d1)) => {
+                    work.push(Step::Combine);
+                    work.push(Step::Visit(b1.clone(), d1.clone()));
+                    work.push(Step::Visit(a1.clone(), c1.clone()));
+                }
+
+                // If we didn't find a structure for subtree equality this is
+                // just a case of `math=[a, b] => 0`
+                _ => results.push(Rc::new(atom(0)))
+            },
+            Step::Combine => {
+                let right = results.pop().expect("Combine pushed only after both sides compute");
+                let left = results.pop().expect("Combine pushed only after both sides compute");
 
-        //If we didn't find a structure for subtree equality this is just a case of `math=[a, b] => 0`
-        _ => Atom(0)
+                // `eq_cell` only ever produces an Atom (0 or 1), so combining
+                // two already-computed sides is itself the Atom/Atom case
+                // one level up.
+                match (&*left, &*right) {
+                    (&Atom(ref a), &Atom(ref b)) => results.push(Rc::new(atom(if a == b { 1 } else { 0 }))),
+                    _ => unreachable!("eq_cell only ever produces an Atom")
+                }
+            }
+        }
     }
+
+    (*results.pop().expect("the initial Visit step always produces a value")).clone()
 }
 
 fn swap(v: &Value) -> Value {
     match v {
-        &Error => Error,
-        &Atom(a) => Atom(a),
-        &Cell(ref a, ref b) => Cell(b.clone(), a.clone())
+        &Error(ref info) => Error(info.clone()),
+        &Atom(ref a) => Atom(a.clone()),
+        &Cell(ref a, ref b) => Cell(b.clone(), a.clone()),
+        &Var(n) => Var(n),
+        &Lambda(ref c) => Lambda(c.clone())
     }
 }
 
 fn eval(v: &Value) -> Rc<Value> {
     match v {
-        &Error => Rc::new(Error),
-        &Atom(a) => Rc::new(Error),
-        &Cell(ref a, ref b) => eval_cell(&a, &b)
-    }
-}
-
-fn eval_cell(a: &Value, b: &Value) -> Rc<Value> {
-    match (a.atom_value(), b) {
-        (None, _) => Rc::new(Error),
-        (Some(0), _) => Rc::new(kind(&b)),
-        (Some(1), _) => Rc::new(sub(&b)),
-        (Some(2), _) => Rc::new(eq(&b)),
-        (Some(3), _) => Rc::new(swap(&b)),
-
-        // eval! [4, [a, [b, c]] => eval! [ eval! [a, b], eval! [a, c] ]
-        (Some(4), &Cell(ref a, ref bc)) => {
-            match **bc {
-                Error => Rc::new(Error),
-                Atom(_) => Rc::new(Error),
-                Cell(ref b, ref c) => Rc::new(eval_cell(
-                    &eval_cell(&a, &b),
-                    &eval_cell(&a, &c)
-                ))
+        &Error(ref info) => Rc::new(Error(info.clone())),
+        &Atom(_) | &Var(_) | &Lambda(_) => Rc::new(err(ErrorKind::TypeMismatch, Some(Rc::new(v.clone())))),
+        &Cell(ref a, ref b) => eval_cell(&a, &b, Rc::new(Env::Empty))
+    }
+}
+
+// A pending step of the trampoline.
+//
+// `Eval(a, b, env)` means "reduce eval! [a, b] under env and push the
+// result"; `Combine` means "pop the two most recent results, which are
+// eval! [a, b] and eval! [a, c] for some opcode-4 dispatch, and re-dispatch
+// them against each other under the env the opcode-4 call itself saw"
+// (mirroring the original `eval_cell(&eval_cell(a, b), &eval_cell(a, c))`
+// recursion). `Resolve(v, env)` means "reduce the arbitrary expression v
+// (which may be a bare Atom, Var or Lambda, not just a Cell) to a value
+// under env"; `Apply` means "pop the two most recent Resolve results, which
+// are the function and argument of an opcode-7 dispatch, and apply one to
+// the other".
+enum Frame {
+    Eval(Rc<Value>, Rc<Value>, Rc<Env>),
+    Combine(Rc<Env>),
+    Resolve(Rc<Value>, Rc<Env>),
+    Apply
+}
+
+/// Outcome of a fuel-bounded evaluation: either it finished, or the step
+/// budget ran out first.
+#[derive(Debug)]
+enum EvalOutcome {
+    Done(Rc<Value>),
+    Partial
+}
+
+// Replaces every `Var` found anywhere in `v` with its bound value from
+// `env`, recursing through `Cell`s without evaluating them. `kind?`/`sub`/
+// `math=`/`swap` (and the Cell-shape checks opcodes 4-6 make on their
+// operand) see `b` as literal data, not a program to reduce, so a lambda
+// body like `[1, [$0, 2]]` needs `$0` replaced with the bound argument
+// before `sub` ever walks its operand — unlike `Frame::Resolve`, which
+// evaluates a Cell as `eval! [a, b]` rather than treating it as data.
+//
+// Iterative (an explicit work/result stack), not plain recursion, for the
+// same reason `eval_trampoline` is: a `b` built from a long chain of
+// nested cells must substitute without overflowing the Rust call stack.
+fn substitute(v: &Rc<Value>, env: &Rc<Env>) -> Rc<Value> {
+    enum Step {
+        Visit(Rc<Value>),
+        BuildCell
+    }
+
+    let mut work = vec![Step::Visit(v.clone())];
+    let mut results: Vec<Rc<Value>> = Vec::new();
+
+    while let Some(step) = work.pop() {
+        match step {
+            Step::Visit(v) => match &*v {
+                &Error(_) | &Atom(_) | &Lambda(_) => results.push(v.clone()),
+                &Var(n) => results.push(match env.get(n) {
+                    Some(bound) => bound,
+                    None => Rc::new(err(ErrorKind::UnboundVariable(n), None))
+                }),
+                &Cell(ref a, ref b) => {
+                    work.push(Step::BuildCell);
+                    work.push(Step::Visit(b.clone()));
+                    work.push(Step::Visit(a.clone()));
+                }
+            },
+            Step::BuildCell => {
+                let b = results.pop().expect("BuildCell pushed only after both sides substitute");
+                let a = results.pop().expect("BuildCell pushed only after both sides substitute");
+                results.push(Rc::new(Cell(a, b)));
+            }
+        }
+    }
+
+    results.pop().expect("the initial Visit step always produces a value")
+}
+
+// Iterative form of `eval_cell`: maintains an explicit work stack of
+// `Frame`s and a value stack of already-reduced results, looping until the
+// work stack is empty instead of recursing on the Rust call stack. This
+// lets a long right-spine of cells (e.g. produced by repeated opcode-4
+// distribution) evaluate without overflowing the stack.
+//
+// `fuel` bounds the number of frames processed; `None` means unbounded.
+fn eval_trampoline(a: Rc<Value>, b: Rc<Value>, env: Rc<Env>, fuel: Option<usize>) -> EvalOutcome {
+    let mut work = vec![Frame::Eval(a, b, env)];
+    let mut values: Vec<Rc<Value>> = Vec::new();
+    let mut fuel = fuel;
+
+    while let Some(frame) = work.pop() {
+        if let Some(ref mut remaining) = fuel {
+            if *remaining == 0 {
+                return EvalOutcome::Partial;
             }
-        },
-
-        // eval! [5, [0, [b, c]]] => b
-        // eval! [5, [1, [b, c]]] => c
-        (Some(5), &Cell(ref a, ref bc)) => {
-            match (a.atom_value(), bc.cell_content()) {
-                (Some(0), Some((ref b, ref c))) => Rc::new(b),
-                (Some(1), Some((ref b, ref c))) => Rc::new(c),
-                _ => Rc::new(Error)
+            *remaining -= 1;
+        }
+
+        match frame {
+            Frame::Eval(a, b, env) => {
+                // No binding in scope at the top level (the common case, and
+                // the only one the pre-lambda tests exercise) means `a`/`b`
+                // can't contain a bound `Var`, so skip the substitution pass
+                // entirely rather than re-copying a possibly huge operand.
+                let a = match &*env {
+                    &Env::Empty => a,
+                    _ => substitute(&a, &env)
+                };
+                let op = opcode(&a);
+
+                // Opcode 6 constructs a closure that captures `env` as-is;
+                // its body is a deferred expression, only resolved once the
+                // closure is later applied (against *its* env extended with
+                // the new argument) — unlike every other opcode's operand,
+                // it must not be substituted here.
+                let b = match (&*env, op) {
+                    (&Env::Empty, _) | (_, Some(6)) => b,
+                    _ => substitute(&b, &env)
+                };
+
+                match (op, &*b) {
+                    (None, _) => values.push(Rc::new(err(ErrorKind::TypeMismatch, Some(a.clone())))),
+                    (Some(0), _) => values.push(Rc::new(kind(&b))),
+                    (Some(1), _) => values.push(Rc::new(sub(&b))),
+                    (Some(2), _) => values.push(Rc::new(eq(&b))),
+                    (Some(3), _) => values.push(Rc::new(swap(&b))),
+
+                    // eval! [4, [a, [b, c]] => eval! [ eval! [a, b], eval! [a, c] ]
+                    (Some(4), &Cell(ref inner_a, ref bc)) => {
+                        match &**bc {
+                            &Cell(ref b, ref c) => {
+                                work.push(Frame::Combine(env.clone()));
+                                work.push(Frame::Eval(inner_a.clone(), c.clone(), env.clone()));
+                                work.push(Frame::Eval(inner_a.clone(), b.clone(), env.clone()));
+                            }
+                            _ => values.push(Rc::new(err(ErrorKind::MalformedOperand, Some(bc.clone()))))
+                        }
+                    },
+
+                    // opcode 4's operand must itself be a cell
+                    (Some(4), _) => values.push(Rc::new(err(ErrorKind::MalformedOperand, Some(b.clone())))),
+
+                    // eval! [5, [0, [b, c]]] => b
+                    // eval! [5, [1, [b, c]]] => c
+                    (Some(5), &Cell(ref sel, ref bc)) => {
+                        match (opcode(&sel), bc.cell_content()) {
+                            (Some(0), Some((ref b, _))) => values.push(b.clone()),
+                            (Some(1), Some((_, ref c))) => values.push(c.clone()),
+                            _ => values.push(Rc::new(err(ErrorKind::MalformedOperand, Some(b.clone()))))
+                        }
+                    }
+
+                    // opcode 5's operand must itself be a cell
+                    (Some(5), _) => values.push(Rc::new(err(ErrorKind::MalformedOperand, Some(b.clone())))),
+
+                    // eval! [6, [arity, body]] => a closure over the current env
+                    (Some(6), &Cell(ref arity, ref body)) => {
+                        match opcode(&arity) {
+                            Some(n) if n >= 1 => values.push(Rc::new(Lambda(Rc::new(Closure {
+                                arity: n,
+                                body: body.clone(),
+                                env: env.clone()
+                            })))),
+                            _ => values.push(Rc::new(err(ErrorKind::MalformedOperand, Some(arity.clone()))))
+                        }
+                    }
+
+                    // opcode 6's operand must itself be a cell
+                    (Some(6), _) => values.push(Rc::new(err(ErrorKind::MalformedOperand, Some(b.clone())))),
+
+                    // eval! [7, [f, arg]] => resolve f and arg under env, then apply
+                    (Some(7), &Cell(ref f, ref arg)) => {
+                        work.push(Frame::Apply);
+                        work.push(Frame::Resolve(arg.clone(), env.clone()));
+                        work.push(Frame::Resolve(f.clone(), env.clone()));
+                    }
+
+                    // opcode 7's operand must itself be a cell
+                    (Some(7), _) => values.push(Rc::new(err(ErrorKind::MalformedOperand, Some(b.clone())))),
+
+                    (Some(op), _) => values.push(Rc::new(err(ErrorKind::UnknownOpcode(op), None)))
+                }
+            }
+            Frame::Combine(env) => {
+                let c = values.pop().expect("Combine pushed only after both operands evaluate");
+                let b = values.pop().expect("Combine pushed only after both operands evaluate");
+                work.push(Frame::Eval(b, c, env));
+            }
+            Frame::Resolve(v, env) => {
+                match &*v {
+                    &Error(ref info) => values.push(Rc::new(Error(info.clone()))),
+                    &Atom(_) | &Lambda(_) => values.push(v.clone()),
+                    &Var(n) => values.push(match env.get(n) {
+                        Some(bound) => bound,
+                        None => Rc::new(err(ErrorKind::UnboundVariable(n), None))
+                    }),
+                    &Cell(ref a, ref b) => work.push(Frame::Eval(a.clone(), b.clone(), env))
+                }
+            }
+            Frame::Apply => {
+                let arg = values.pop().expect("Apply pushed only after both operands resolve");
+                let f = values.pop().expect("Apply pushed only after both operands resolve");
+                match &*f {
+                    &Error(ref info) => values.push(Rc::new(Error(info.clone()))),
+                    &Lambda(ref closure) => {
+                        let new_env = Rc::new(Env::Frame(arg, closure.env.clone()));
+                        if closure.arity <= 1 {
+                            work.push(Frame::Resolve(closure.body.clone(), new_env));
+                        } else {
+                            values.push(Rc::new(Lambda(Rc::new(Closure {
+                                arity: closure.arity - 1,
+                                body: closure.body.clone(),
+                                env: new_env
+                            }))));
+                        }
+                    }
+                    _ => values.push(Rc::new(err(ErrorKind::NotAFunction, Some(f.clone()))))
+                }
             }
         }
+    }
+
+    EvalOutcome::Done(values.pop().expect("the initial Eval frame always produces a value"))
+}
 
-        _ => Rc::new(Error)
+fn eval_cell(a: &Value, b: &Value, env: Rc<Env>) -> Rc<Value> {
+    match eval_trampoline(Rc::new(a.clone()), Rc::new(b.clone()), env, None) {
+        EvalOutcome::Done(v) => v,
+        EvalOutcome::Partial => unreachable!("unfueled eval_trampoline never runs out of fuel")
+    }
+}
+
+/// Like `eval`, but bounded to `steps` reduction frames. Returns
+/// `EvalOutcome::Partial` instead of looping forever when a non-terminating
+/// opcode-4 expansion would otherwise run away.
+fn eval_with_fuel(v: &Value, steps: usize) -> EvalOutcome {
+    match v {
+        &Error(ref info) => EvalOutcome::Done(Rc::new(Error(info.clone()))),
+        &Atom(_) | &Var(_) | &Lambda(_) => EvalOutcome::Done(Rc::new(err(ErrorKind::TypeMismatch, Some(Rc::new(v.clone()))))),
+        &Cell(ref a, ref b) => eval_trampoline(Rc::new((**a).clone()), Rc::new((**b).clone()), Rc::new(Env::Empty), Some(steps))
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::{ Value, kind, sub, eq, swap, eval };
+    use super::{ Value, kind, sub, eq, swap, eval, eval_with_fuel, EvalOutcome, parse, err, ErrorKind, atom };
+    use super::Value::{ Error, Cell, Var };
     use std::rc::{ Rc };
 
+    // `[6 [arity body]]` builds a closure, `[7 [f arg]]` applies one.
+    fn make_closure(arity: i32, body: Value) -> Value {
+        Cell(Rc::new(atom(6)), Rc::new(Cell(Rc::new(atom(arity)), Rc::new(body))))
+    }
+
+    fn apply(f: Value, arg: Value) -> Value {
+        Cell(Rc::new(atom(7)), Rc::new(Cell(Rc::new(f), Rc::new(arg))))
+    }
+
     #[test]
     fn kind_of_error_is_error() {
         // eval! [0, Error] => kind? Error => Error
         let r = eval(&Cell(
-            Rc::new(Atom(0)),
-            Rc::new(Error)
+            Rc::new(atom(0)),
+            Rc::new(err(ErrorKind::Literal, None))
         ));
 
-        match r {
-            Error => assert!(true),
+        match *r {
+            Error(_) => assert!(true),
             _ => assert!(false)
         };
     }
@@ -183,49 +781,40 @@ mod tests {
     fn kind_of_cell_is_cell() {
         // eval! [0, Error] => kind? [1, 2] => 1
         let r = eval(&Cell(
-            Rc::new(Atom(0)),
+            Rc::new(atom(0)),
             Rc::new(Cell(
-                Rc::new(Atom(1)),
-                Rc::new(Atom(2)),
+                Rc::new(atom(1)),
+                Rc::new(atom(2)),
             ))
         ));
 
-        match r {
-            Atom(1) => assert!(true),
-            _ => assert!(false)
-        };
+        assert_eq!(r.atom_value(), Some(1));
     }
 
     #[test]
     fn kind_of_atom_is_atom() {
         // eval! [0, 1] => kind? 1 => 0
         let r = eval(&Cell(
-            Rc::new(Atom(0)),
-            Rc::new(Atom(1))
+            Rc::new(atom(0)),
+            Rc::new(atom(1))
         ));
 
-        match r {
-            Atom(0) => assert!(true),
-            _ => assert!(false)
-        };
+        assert_eq!(r.atom_value(), Some(0));
     }
 
     #[test]
     fn math_sub_atom_is_negate() {
-        let r = sub(&Atom(-1));
+        let r = sub(&atom(-1));
 
-        match r {
-            Atom(1) => assert!(true),
-            _ => assert!(false)
-        }
+        assert_eq!(r.atom_value(), Some(1));
     }
 
     #[test]
     fn math_sub_error_is_error() {
-        let r = sub(&Error);
+        let r = sub(&err(ErrorKind::Literal, None));
 
         match r {
-            Error => assert!(true),
+            Error(_) => assert!(true),
             _ => assert!(false)
         }
     }
@@ -233,24 +822,21 @@ mod tests {
     #[test]
     fn math_sub_cell_is_sub() {
         let r = sub(&Cell(
-            Rc::new(Atom(1)),
-            Rc::new(Atom(2)),
+            Rc::new(atom(1)),
+            Rc::new(atom(2)),
         ));
 
-        match r {
-            Atom(-1) => assert!(true),
-            _ => assert!(false)
-        }
+        assert_eq!(r.atom_value(), Some(-1));
     }
 
     #[test]
     fn math_sub_atom_from_cell() {
         let r = sub(&Cell(
             Rc::new(Cell(
-                Rc::new(Atom(1)),
-                Rc::new(Atom(2))
+                Rc::new(atom(1)),
+                Rc::new(atom(2))
             )),
-            Rc::new(Atom(3))
+            Rc::new(atom(3))
         ));
 
         match r {
@@ -267,10 +853,10 @@ mod tests {
     #[test]
     fn math_sub_cell_from_atom() {
         let r = sub(&Cell(
-            Rc::new(Atom(3)),
+            Rc::new(atom(3)),
             Rc::new(Cell(
-                Rc::new(Atom(1)),
-                Rc::new(Atom(2))
+                Rc::new(atom(1)),
+                Rc::new(atom(2))
             )),
         ));
 
@@ -289,12 +875,12 @@ mod tests {
     fn math_sub_cell_from_cell() {
         let r = sub(&Cell(
             Rc::new(Cell(
-                Rc::new(Atom(1)),
-                Rc::new(Atom(2))
+                Rc::new(atom(1)),
+                Rc::new(atom(2))
             )),
             Rc::new(Cell(
-                Rc::new(Atom(3)),
-                Rc::new(Atom(4))
+                Rc::new(atom(3)),
+                Rc::new(atom(4))
             )),
         ));
 
@@ -312,57 +898,134 @@ mod tests {
     }
 
     #[test]
-    fn eq_error_is_error() {
-        match eq(&Error) {
-            Error => assert!(true),
+    fn math_sub_beyond_i32_range_does_not_overflow() {
+        let r = sub(&Cell(Rc::new(atom(i32::MIN)), Rc::new(atom(1))));
+
+        // i32::MIN - 1 doesn't fit in an i32, but it must not wrap around to
+        // a positive number the way plain `i32` subtraction would.
+        assert_eq!(r.atom_value(), None);
+        assert_eq!(format!("{}", r), "-2147483649");
+    }
+
+    #[test]
+    fn math_sub_does_not_overflow_the_stack_on_a_deep_cell() {
+        let depth = 200_000;
+        let mut chain = Rc::new(atom(1));
+        for _ in 0..depth {
+            chain = Rc::new(Cell(chain, Rc::new(atom(1))));
+        }
+
+        let r = sub(&Cell(chain.clone(), Rc::new(atom(1))));
+
+        match &r {
+            &Cell(_, _) => assert!(true),
             _ => assert!(false)
         }
+
+        // `chain` and `r` are both `depth`-deep nested `Cell`s; the derived
+        // `Drop` recurses the same way the old `sub_cell` did, for a reason
+        // unrelated to what's under test here, so leak them instead of
+        // tearing them down (see `eval_does_not_overflow_the_stack_on_a_deep_right_spine`).
+        std::mem::forget(chain);
+        std::mem::forget(r);
     }
 
     #[test]
-    fn eq_atom_is_error() {
-        match eq(&Atom(1)) {
-            Error => assert!(true),
+    fn eval_sub_does_not_overflow_the_stack_on_a_deep_cell() {
+        // Regression: `eval`'s opcode-1 dispatch calls `sub`/`sub_cell`
+        // directly rather than going through the trampoline, so a deep
+        // operand must still not overflow the stack even though `eval`
+        // itself never recurses on the Rust call stack.
+        let depth = 200_000;
+        let mut chain = Rc::new(atom(1));
+        for _ in 0..depth {
+            chain = Rc::new(Cell(chain, Rc::new(atom(1))));
+        }
+        let program = Cell(Rc::new(atom(1)), chain.clone());
+
+        let r = eval(&program);
+
+        match &*r {
+            &Cell(_, _) => assert!(true),
             _ => assert!(false)
         }
+
+        std::mem::forget(chain);
+        std::mem::forget(program);
+        std::mem::forget(r);
     }
 
     #[test]
-    fn eq_cell_is_equal_with_equal_atoms() {
-        match eq(&Cell(Rc::new(Atom(1)), Rc::new(Atom(1)))) {
-            Atom(1) => assert!(true),
+    fn eq_error_is_error() {
+        match eq(&err(ErrorKind::Literal, None)) {
+            Error(_) => assert!(true),
             _ => assert!(false)
         }
     }
 
     #[test]
-    fn eq_cell_is_unequal_with_unequal_atoms() {
-        match eq(&Cell(Rc::new(Atom(1)), Rc::new(Atom(2)))) {
-            Atom(0) => assert!(true),
+    fn eq_atom_is_error() {
+        match eq(&atom(1)) {
+            Error(_) => assert!(true),
             _ => assert!(false)
         }
     }
 
+    #[test]
+    fn eq_cell_is_equal_with_equal_atoms() {
+        let r = eq(&Cell(Rc::new(atom(1)), Rc::new(atom(1))));
+        assert_eq!(r.atom_value(), Some(1));
+    }
+
+    #[test]
+    fn eq_cell_is_unequal_with_unequal_atoms() {
+        let r = eq(&Cell(Rc::new(atom(1)), Rc::new(atom(2))));
+        assert_eq!(r.atom_value(), Some(0));
+    }
+
     #[test]
     fn eq_cell_is_equal_with_equal_subtrees() {
-        match eq(&Cell(
+        let r = eq(&Cell(
             Rc::new(Cell(
-                Rc::new(Atom(1)),
-                Rc::new(Atom(2)),
+                Rc::new(atom(1)),
+                Rc::new(atom(2)),
             )),
             Rc::new(Cell(
-                Rc::new(Atom(1)),
-                Rc::new(Atom(2)),
+                Rc::new(atom(1)),
+                Rc::new(atom(2)),
             ))
-        )) {
-            Atom(1) => assert!(true),
-            _ => assert!(false)
+        ));
+        assert_eq!(r.atom_value(), Some(1));
+    }
+
+    #[test]
+    fn math_eq_does_not_overflow_the_stack_on_a_deep_cell() {
+        // Two independently-allocated chains (not `Rc::clone`d) of the same
+        // shape, so `eq_cell` actually walks both trees structurally instead
+        // of the comparison being masked by pointer equality anywhere.
+        fn build_chain(depth: usize) -> Rc<Value> {
+            let mut chain = Rc::new(atom(1));
+            for _ in 0..depth {
+                chain = Rc::new(Cell(chain, Rc::new(atom(1))));
+            }
+            chain
         }
+
+        let depth = 200_000;
+        let left = build_chain(depth);
+        let right = build_chain(depth);
+
+        let r = eq(&Cell(left.clone(), right.clone()));
+
+        assert_eq!(r.atom_value(), Some(1));
+
+        std::mem::forget(left);
+        std::mem::forget(right);
     }
 
     #[test]
     fn swap_cell_swaps_sides() {
-        let v = swap(&Cell(Rc::new(Atom(1)), Rc::new(Atom(2))));
+        let v = swap(&Cell(Rc::new(atom(1)), Rc::new(atom(2))));
         match v {
             Cell(ref a, ref b) => {
                 match (a.atom_value(), b.atom_value()) {
@@ -373,4 +1036,284 @@ mod tests {
             _ => assert!(false)
         }
     }
+
+    #[test]
+    fn lambda_applies_to_its_argument() {
+        // eval! [7, [ eval! [6, [1, $0]], 5 ]] => 5
+        let identity = make_closure(1, Var(0));
+        let r = eval(&apply(identity, atom(5)));
+
+        assert_eq!(r.atom_value(), Some(5));
+    }
+
+    #[test]
+    fn lambda_captures_its_defining_env() {
+        // (\x -> (\y -> x))(1)(2) => 1: the inner closure must still see the
+        // outer argument after the outer application has returned.
+        let inner = make_closure(1, Var(1));
+        let outer = make_closure(1, inner);
+        let applied_once = apply(outer, atom(1));
+        let r = eval(&apply(applied_once, atom(2)));
+
+        assert_eq!(r.atom_value(), Some(1));
+    }
+
+    #[test]
+    fn lambda_of_arity_two_curries() {
+        // (\x y -> x)(1)(2) => 1, via a single two-argument closure rather
+        // than two nested one-argument ones.
+        let k = make_closure(2, Var(1));
+        let applied_once = apply(k, atom(1));
+        let r = eval(&apply(applied_once, atom(2)));
+
+        assert_eq!(r.atom_value(), Some(1));
+    }
+
+    #[test]
+    fn lambda_applies_an_opcode_to_its_argument() {
+        // \x -> kind?(x), applied to 5 => 0 (5 is an atom). The bound
+        // argument sits directly in the opcode-0 operand position, so
+        // dispatch has to substitute $0 before `kind` ever sees it.
+        let kind_of_x = make_closure(1, Cell(Rc::new(atom(0)), Rc::new(Var(0))));
+        let r = eval(&apply(kind_of_x, atom(5)));
+
+        assert_eq!(r.atom_value(), Some(0));
+    }
+
+    #[test]
+    fn lambda_does_math_on_its_argument() {
+        // \x -> x - 2, applied to 10 => 8. Here $0 is nested one level
+        // inside the opcode-1 operand, not the operand itself.
+        let minus_two = make_closure(1, Cell(Rc::new(atom(1)), Rc::new(Cell(Rc::new(Var(0)), Rc::new(atom(2))))));
+        let r = eval(&apply(minus_two, atom(10)));
+
+        assert_eq!(r.atom_value(), Some(8));
+    }
+
+    #[test]
+    fn applying_a_non_function_is_an_error() {
+        let r = eval(&apply(atom(1), atom(2)));
+
+        match *r {
+            Error(_) => assert!(true),
+            _ => assert!(false)
+        }
+    }
+
+    #[test]
+    fn unbound_var_is_an_error() {
+        // The body references an argument one level further out than this
+        // application actually provides.
+        let out_of_scope = make_closure(1, Var(1));
+        let r = eval(&apply(out_of_scope, atom(1)));
+
+        match *r {
+            Error(_) => assert!(true),
+            _ => assert!(false)
+        }
+    }
+
+    #[test]
+    fn eval_accepts_a_parsed_program() {
+        // eval! [4, [0, [1, 2]]] => eval! [eval! [0, 1], eval! [0, 2]]
+        //                        => eval! [0, 0] => kind? 0 => 0
+        let program = parse("[4 [0 [1 2]]]").unwrap();
+        let r = eval(&program);
+
+        assert_eq!(r.atom_value(), Some(0));
+    }
+
+    #[test]
+    fn eval_does_not_overflow_the_stack_on_a_deep_right_spine() {
+        // Build, iteratively, a chain where each level re-triggers opcode 4:
+        //   chain(0)   = 1
+        //   chain(n)   = [4 [1 chain(n-1)]]
+        // so `eval! [4, chain(n)]` recurses the trampoline's *work stack*
+        // exactly `n` levels deep instead of the Rust call stack.
+        let depth = 100_000;
+        let mut tail = Rc::new(atom(1));
+        for _ in 0..depth {
+            tail = Rc::new(Cell(Rc::new(atom(4)), Rc::new(Cell(Rc::new(atom(1)), tail))));
+        }
+        let program = Cell(Rc::new(atom(4)), tail);
+
+        let r = eval(&program);
+
+        match *r {
+            Error(_) => assert!(true),
+            _ => assert!(false)
+        }
+
+        // `program`'s nested `Rc<Value>` chain is `depth` cells long, and the
+        // derived `Drop` recurses the same way `eval_cell` used to; dropping
+        // it here would overflow the stack for a reason unrelated to this
+        // test, so leak it instead of tearing it down.
+        std::mem::forget(program);
+    }
+
+    #[test]
+    fn eval_with_fuel_reports_partial_when_the_budget_runs_out() {
+        let program = parse("[4 [0 [1 2]]]").unwrap();
+
+        match eval_with_fuel(&program, 0) {
+            EvalOutcome::Partial => assert!(true),
+            _ => assert!(false)
+        }
+
+        match eval_with_fuel(&program, 100) {
+            EvalOutcome::Done(v) => assert_eq!(v.atom_value(), Some(0)),
+            EvalOutcome::Partial => assert!(false)
+        }
+    }
+
+    #[test]
+    fn display_prints_minimal_right_associative_form() {
+        let v = Cell(
+            Rc::new(Cell(Rc::new(atom(1)), Rc::new(atom(2)))),
+            Rc::new(Cell(Rc::new(atom(3)), Rc::new(atom(4))))
+        );
+
+        assert_eq!(format!("{}", v), "[[1 2] 3 4]");
+    }
+
+    #[test]
+    fn equal_atoms_compare_equal() {
+        assert_eq!(atom(1), atom(1));
+        assert_ne!(atom(1), atom(2));
+    }
+
+    #[test]
+    fn errors_are_always_equal_regardless_of_kind() {
+        assert_eq!(err(ErrorKind::Literal, None), err(ErrorKind::DivergentEq, None));
+    }
+
+    #[test]
+    fn cells_compare_componentwise() {
+        let a = Cell(Rc::new(atom(1)), Rc::new(atom(2)));
+        let b = Cell(Rc::new(atom(1)), Rc::new(atom(2)));
+        let c = Cell(Rc::new(atom(1)), Rc::new(atom(3)));
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn value_equality_does_not_overflow_the_stack_on_a_deep_cell() {
+        // Two independently-allocated chains (not `Rc::clone`d): `Rc`'s own
+        // `PartialEq` short-circuits on pointer equality, which would hide
+        // a stack overflow in a naive test built by cloning a single tree.
+        fn build_chain(depth: usize) -> Rc<Value> {
+            let mut chain = Rc::new(atom(1));
+            for _ in 0..depth {
+                chain = Rc::new(Cell(chain, Rc::new(atom(1))));
+            }
+            chain
+        }
+
+        let depth = 200_000;
+        let a = build_chain(depth);
+        let b = build_chain(depth);
+
+        assert!(*a == *b);
+
+        std::mem::forget(a);
+        std::mem::forget(b);
+    }
+
+    #[test]
+    fn value_compares_against_bare_i32() {
+        assert_eq!(atom(1), 1);
+        assert_eq!(1, atom(1));
+        assert_ne!(atom(1), 2);
+        assert_ne!(Cell(Rc::new(atom(1)), Rc::new(atom(2))), 1);
+    }
+
+    #[test]
+    fn value_works_as_a_hashset_member() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        set.insert(atom(1));
+        set.insert(Cell(Rc::new(atom(1)), Rc::new(atom(2))));
+        set.insert(err(ErrorKind::Literal, None));
+
+        assert!(set.contains(&atom(1)));
+        assert!(set.contains(&Cell(Rc::new(atom(1)), Rc::new(atom(2)))));
+
+        // Every `Error` is equal (and so must hash the same), regardless of
+        // kind/subtree.
+        assert!(set.contains(&err(ErrorKind::DivergentEq, None)));
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn value_hash_does_not_overflow_the_stack_on_a_deep_cell() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{ Hash, Hasher };
+
+        // Same independent-construction caveat as
+        // `value_equality_does_not_overflow_the_stack_on_a_deep_cell`.
+        fn build_chain(depth: usize) -> Rc<Value> {
+            let mut chain = Rc::new(atom(1));
+            for _ in 0..depth {
+                chain = Rc::new(Cell(chain, Rc::new(atom(1))));
+            }
+            chain
+        }
+
+        let depth = 200_000;
+        let a = build_chain(depth);
+        let b = build_chain(depth);
+
+        let mut hasher_a = DefaultHasher::new();
+        a.hash(&mut hasher_a);
+        let mut hasher_b = DefaultHasher::new();
+        b.hash(&mut hasher_b);
+
+        assert_eq!(hasher_a.finish(), hasher_b.finish());
+
+        std::mem::forget(a);
+        std::mem::forget(b);
+    }
+
+    #[test]
+    fn ord_follows_fixed_variant_order_then_value() {
+        assert!(err(ErrorKind::Literal, None) < atom(0));
+        assert!(atom(0) < Cell(Rc::new(atom(1)), Rc::new(atom(2))));
+        assert!(atom(1) < atom(2));
+        assert!(
+            Cell(Rc::new(atom(1)), Rc::new(atom(1))) < Cell(Rc::new(atom(1)), Rc::new(atom(2)))
+        );
+    }
+
+    #[test]
+    fn value_ord_does_not_overflow_the_stack_on_a_deep_cell() {
+        // Same independent-construction caveat as
+        // `value_equality_does_not_overflow_the_stack_on_a_deep_cell`.
+        fn build_chain(depth: usize) -> Rc<Value> {
+            let mut chain = Rc::new(atom(1));
+            for _ in 0..depth {
+                chain = Rc::new(Cell(chain, Rc::new(atom(1))));
+            }
+            chain
+        }
+
+        let depth = 200_000;
+        let a = build_chain(depth);
+        let b = build_chain(depth);
+
+        assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
+
+        std::mem::forget(a);
+        std::mem::forget(b);
+    }
+
+    #[test]
+    fn display_and_parse_round_trip() {
+        let v = parse("[1 [2 3] 4 !]").unwrap();
+        let printed = format!("{}", v);
+        let reparsed = parse(&printed).unwrap();
+
+        assert_eq!(printed, format!("{}", reparsed));
+    }
 }
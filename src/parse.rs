@@ -0,0 +1,218 @@
+use std::rc::Rc;
+
+use crate::Value::{self, Atom, Cell};
+
+// Reader for the concrete syntax:
+//
+//   integer      := '-'? digit+
+//   error-lit    := '!' | '_'
+//   cell         := '[' value value+ ']'     (right-associative: [a b c] == [a [b c]])
+//   value        := integer | error-lit | cell
+//
+// Tokens carry the byte offset they started at so `ParseError` can point back
+// into the source.
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    // Sign, plus the run of ASCII decimal digits, parsed lazily into a
+    // `BigInt` in `parse_value` so the tokenizer never has to care how many
+    // digits a literal has.
+    Int(bool, String),
+    ErrorLit,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ParseError {
+    UnexpectedChar(usize, char),
+    UnexpectedEnd(usize),
+    TooFewCellElements(usize),
+    UnclosedCell(usize),
+    TrailingInput(usize),
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    let bytes = input.as_bytes();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let c = bytes[pos] as char;
+
+        if c.is_whitespace() {
+            pos += 1;
+            continue;
+        }
+
+        match c {
+            '[' => {
+                tokens.push((Token::Open, pos));
+                pos += 1;
+            }
+            ']' => {
+                tokens.push((Token::Close, pos));
+                pos += 1;
+            }
+            '!' | '_' => {
+                tokens.push((Token::ErrorLit, pos));
+                pos += 1;
+            }
+            '-' | '0'..='9' => {
+                let start = pos;
+                if c == '-' {
+                    pos += 1;
+                }
+                let digits_start = pos;
+                while pos < bytes.len() && (bytes[pos] as char).is_ascii_digit() {
+                    pos += 1;
+                }
+                if pos == digits_start {
+                    return Err(ParseError::UnexpectedChar(start, c));
+                }
+                let digits = input[digits_start..pos].to_string();
+                tokens.push((Token::Int(c == '-', digits), start));
+            }
+            _ => return Err(ParseError::UnexpectedChar(pos, c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_value(tokens: &[(Token, usize)], pos: &mut usize, eof: usize) -> Result<Rc<Value>, ParseError> {
+    match tokens.get(*pos) {
+        None => Err(ParseError::UnexpectedEnd(eof)),
+        Some((Token::Int(negative, digits), _)) => {
+            *pos += 1;
+            Ok(Rc::new(Atom(Rc::new(crate::BigInt::from_decimal_digits(*negative, digits)))))
+        }
+        Some((Token::ErrorLit, _)) => {
+            *pos += 1;
+            Ok(Rc::new(crate::err(crate::ErrorKind::Literal, None)))
+        }
+        Some((Token::Close, p)) => Err(ParseError::UnexpectedChar(*p, ']')),
+        Some((Token::Open, open_pos)) => {
+            let open_pos = *open_pos;
+            *pos += 1;
+
+            let mut elements = Vec::new();
+            loop {
+                match tokens.get(*pos) {
+                    None => return Err(ParseError::UnclosedCell(open_pos)),
+                    Some((Token::Close, _)) => {
+                        *pos += 1;
+                        break;
+                    }
+                    _ => elements.push(parse_value(tokens, pos, eof)?),
+                }
+            }
+
+            if elements.len() < 2 {
+                return Err(ParseError::TooFewCellElements(open_pos));
+            }
+
+            // Right-associative fold: [a b c d] => Cell(a, Cell(b, Cell(c, d)))
+            let mut iter = elements.into_iter().rev();
+            let mut acc = iter.next().unwrap();
+            for element in iter {
+                acc = Rc::new(Cell(element, acc));
+            }
+            Ok(acc)
+        }
+    }
+}
+
+pub(crate) fn parse(input: &str) -> Result<Rc<Value>, ParseError> {
+    let tokens = tokenize(input)?;
+    let mut pos = 0;
+    let value = parse_value(&tokens, &mut pos, input.len())?;
+
+    match tokens.get(pos) {
+        None => Ok(value),
+        Some((_, p)) => Err(ParseError::TrailingInput(*p)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value::{Atom, Cell, Error};
+    use crate::atom;
+
+    // Structural comparison over the shapes the reader produces (errors,
+    // atoms, cells); on those it agrees with `PartialEq for Value`.
+    fn same(a: &Value, b: &Value) -> bool {
+        match (a, b) {
+            (Error(_), Error(_)) => true,
+            (Atom(a), Atom(b)) => a == b,
+            (Cell(a1, b1), Cell(a2, b2)) => same(a1, a2) && same(b1, b2),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn parses_atom() {
+        assert_eq!(parse("1").unwrap().atom_value(), Some(1));
+        assert_eq!(parse("-5").unwrap().atom_value(), Some(-5));
+    }
+
+    #[test]
+    fn parses_atom_beyond_i32_range() {
+        let v = parse("123456789012345678901234567890").unwrap();
+        assert_eq!(v.atom_value(), None);
+        assert_eq!(format!("{}", v), "123456789012345678901234567890");
+    }
+
+    #[test]
+    fn parses_error_literal() {
+        assert!(matches!(*parse("!").unwrap(), Error(_)));
+        assert!(matches!(*parse("_").unwrap(), Error(_)));
+    }
+
+    #[test]
+    fn parses_right_associative_cell() {
+        let v = parse("[1 2 3]").unwrap();
+        let expected = Cell(Rc::new(atom(1)), Rc::new(Cell(Rc::new(atom(2)), Rc::new(atom(3)))));
+        assert!(same(&v, &expected));
+    }
+
+    #[test]
+    fn parses_left_nested_cell() {
+        let v = parse("[[1 2] 3]").unwrap();
+        let expected = Cell(Rc::new(Cell(Rc::new(atom(1)), Rc::new(atom(2)))), Rc::new(atom(3)));
+        assert!(same(&v, &expected));
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        let v = parse("[1 [2 3] 4]").unwrap();
+        let printed = format!("{}", v);
+        let reparsed = parse(&printed).unwrap();
+        assert!(same(&v, &reparsed));
+    }
+
+    #[test]
+    fn rejects_unclosed_cell() {
+        match parse("[1 2") {
+            Err(ParseError::UnclosedCell(0)) => {}
+            other => panic!("expected UnclosedCell(0), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_single_element_cell() {
+        match parse("[1]") {
+            Err(ParseError::TooFewCellElements(0)) => {}
+            other => panic!("expected TooFewCellElements(0), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_char() {
+        match parse("[1 x]") {
+            Err(ParseError::UnexpectedChar(3, 'x')) => {}
+            other => panic!("expected UnexpectedChar(3, 'x'), got {:?}", other),
+        }
+    }
+}